@@ -0,0 +1,235 @@
+//! A reorg-aware canonical-state indexing subsystem.
+//!
+//! The example `main` just `dbg!`s the `Reorg`/`Commit` notifications coming off
+//! `subscribe_to_canonical_state()`. That is fine for eyeballing the stream but
+//! useless for building derived state — balances, logs, token transfers — which
+//! has to survive reorgs. [`CanonStateRunner`] drives the stream into a
+//! [`CanonStateHandler`]: on a commit it hands the handler the newly canonical
+//! blocks and their receipts; on a reorg it first replays the reverted blocks so
+//! the handler can roll its indexes back before the new chain is applied.
+//!
+//! Before attaching the live stream the runner backfills from the handler's last
+//! processed block number using the provider, so a consumer that restarts — or
+//! that missed notifications while it was busy — catches up to the tip without a
+//! gap.
+
+use std::collections::VecDeque;
+
+use tokio::sync::broadcast::error::RecvError;
+
+use reth_errors::ProviderError;
+use reth_primitives::{Receipt, SealedBlockWithSenders};
+use reth_provider::{
+    BlockNumReader, BlockReader, CanonStateNotification, CanonStateSubscriptions, ReceiptProvider,
+};
+
+/// Default number of backfilled blocks buffered before they are flushed to the
+/// handler in a single [`CanonStateHandler::on_commit`] call.
+pub const DEFAULT_BACKFILL_BUFFER: usize = 512;
+
+/// Consumer of canonical-state changes.
+///
+/// Blocks are always delivered in ascending number order. A reorg surfaces as an
+/// [`on_revert`](CanonStateHandler::on_revert) for the blocks leaving the
+/// canonical chain, immediately followed by an
+/// [`on_commit`](CanonStateHandler::on_commit) for the blocks taking their place,
+/// so a handler can roll its derived state back and then forward atomically.
+pub trait CanonStateHandler {
+    /// Error raised while applying a change; aborts the runner.
+    type Error: std::error::Error;
+
+    /// Apply `blocks` — and the matching `receipts` — that became canonical.
+    fn on_commit(
+        &mut self,
+        blocks: &[SealedBlockWithSenders],
+        receipts: &[Vec<Option<Receipt>>],
+    ) -> Result<(), Self::Error>;
+
+    /// Roll back `blocks` that a reorg removed from the canonical chain. They are
+    /// passed in ascending order; a handler peeling state off a stack will want
+    /// to iterate them in reverse.
+    fn on_revert(&mut self, blocks: &[SealedBlockWithSenders]) -> Result<(), Self::Error>;
+
+    /// The highest block number the handler has durably processed, or `None` if
+    /// it has never run. Used to pick the resync start point.
+    fn last_processed_block(&self) -> Option<u64>;
+}
+
+/// Drives `subscribe_to_canonical_state()` into a [`CanonStateHandler`], with a
+/// startup backfill so the handler is caught up to the tip before the live
+/// stream is attached.
+pub struct CanonStateRunner<P, H> {
+    provider: P,
+    handler: H,
+    backfill_buffer: usize,
+    /// Highest block number handed to the handler so far. Commits at or below
+    /// it are skipped so the backfill and the live stream can't both deliver a
+    /// block that became canonical during startup.
+    last_applied: Option<u64>,
+}
+
+impl<P, H> CanonStateRunner<P, H>
+where
+    P: BlockReader + ReceiptProvider + BlockNumReader + CanonStateSubscriptions,
+    H: CanonStateHandler,
+{
+    /// Create a runner with the default backfill buffer.
+    pub fn new(provider: P, handler: H) -> Self {
+        Self {
+            provider,
+            handler,
+            backfill_buffer: DEFAULT_BACKFILL_BUFFER,
+            last_applied: None,
+        }
+    }
+
+    /// Override the number of backfilled blocks buffered before each flush.
+    pub fn with_backfill_buffer(mut self, buffer: usize) -> Self {
+        self.backfill_buffer = buffer.max(1);
+        self
+    }
+
+    /// Backfill from the handler's last processed block up to the current tip,
+    /// then consume canonical-state notifications until the stream closes.
+    ///
+    /// If the runner falls behind and the broadcast channel drops notifications
+    /// (`Lagged`), the backfill is re-run to close the gap from `last_applied`
+    /// before the live stream is resumed, so derived indexes can't silently
+    /// diverge after a lag.
+    pub async fn run(mut self) -> Result<(), CanonRunnerError<H::Error>> {
+        // Subscribe before backfilling so no commit that lands during the
+        // backfill is dropped on the floor; the channel buffers it for us.
+        let mut stream = self.provider.subscribe_to_canonical_state();
+
+        self.backfill()?;
+
+        loop {
+            match stream.recv().await {
+                Ok(CanonStateNotification::Reorg { old, new }) => {
+                    let reverted = blocks(&old);
+                    self.handler
+                        .on_revert(&reverted)
+                        .map_err(CanonRunnerError::Handler)?;
+                    // The reorg replaces blocks at heights we have already
+                    // applied, so drop the dedup watermark below the reorg point
+                    // or the replacement commit would be skipped as "seen".
+                    if let Some(first) = reverted.first() {
+                        self.last_applied = first.number.checked_sub(1);
+                    }
+                    self.commit_chain(&new)?;
+                }
+                Ok(CanonStateNotification::Commit { new }) => {
+                    self.commit_chain(&new)?;
+                }
+                // We fell behind and missed notifications — resync from the last
+                // applied block before resuming the live stream.
+                Err(RecvError::Lagged(_)) => self.backfill()?,
+                Err(RecvError::Closed) => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read the canonical range `(start, best]` from the provider and replay it
+    /// through the handler in `backfill_buffer`-sized chunks. `start` is
+    /// `last_applied`, or the handler's last processed block before the runner
+    /// has applied anything; with neither there is nothing to backfill.
+    fn backfill(&mut self) -> Result<(), CanonRunnerError<H::Error>> {
+        let best = self.provider.best_block_number().map_err(CanonRunnerError::Provider)?;
+        let start = self.last_applied.or_else(|| self.handler.last_processed_block());
+        let mut next = match start {
+            Some(last) if last >= best => return Ok(()),
+            Some(last) => last + 1,
+            None => return Ok(()),
+        };
+
+        let mut buf_blocks = VecDeque::with_capacity(self.backfill_buffer);
+        let mut buf_receipts = VecDeque::with_capacity(self.backfill_buffer);
+        while next <= best {
+            let Some(block) = self
+                .provider
+                .sealed_block_with_senders(next.into(), Default::default())
+                .map_err(CanonRunnerError::Provider)?
+            else {
+                break;
+            };
+            let receipts = self
+                .provider
+                .receipts_by_block(next.into())
+                .map_err(CanonRunnerError::Provider)?
+                .unwrap_or_default()
+                .into_iter()
+                .map(Some)
+                .collect();
+
+            buf_blocks.push_back(block);
+            buf_receipts.push_back(receipts);
+            if buf_blocks.len() >= self.backfill_buffer {
+                self.flush(&mut buf_blocks, &mut buf_receipts)?;
+            }
+            next += 1;
+        }
+        self.flush(&mut buf_blocks, &mut buf_receipts)
+    }
+
+    /// Flush a backfill buffer to the handler as a single commit.
+    fn flush(
+        &mut self,
+        blocks: &mut VecDeque<SealedBlockWithSenders>,
+        receipts: &mut VecDeque<Vec<Option<Receipt>>>,
+    ) -> Result<(), CanonRunnerError<H::Error>> {
+        let blocks: Vec<_> = blocks.drain(..).collect();
+        let receipts: Vec<_> = receipts.drain(..).collect();
+        self.apply_commit(blocks, receipts)
+    }
+
+    /// Hand a committed chain's blocks and receipts to the handler.
+    fn commit_chain(
+        &mut self,
+        chain: &reth_provider::Chain,
+    ) -> Result<(), CanonRunnerError<H::Error>> {
+        self.apply_commit(blocks(chain), chain.receipts().to_vec())
+    }
+
+    /// Deliver a commit, dropping any block at or below [`last_applied`] so the
+    /// backfill/live boundary can't double-fire `on_commit`, and advance the
+    /// watermark to the highest block delivered.
+    fn apply_commit(
+        &mut self,
+        blocks: Vec<SealedBlockWithSenders>,
+        receipts: Vec<Vec<Option<Receipt>>>,
+    ) -> Result<(), CanonRunnerError<H::Error>> {
+        let (blocks, receipts): (Vec<_>, Vec<_>) = match self.last_applied {
+            Some(last) => blocks
+                .into_iter()
+                .zip(receipts)
+                .filter(|(block, _)| block.number > last)
+                .unzip(),
+            None => (blocks, receipts),
+        };
+        if blocks.is_empty() {
+            return Ok(());
+        }
+        self.last_applied = blocks.last().map(|block| block.number);
+        self.handler
+            .on_commit(&blocks, &receipts)
+            .map_err(CanonRunnerError::Handler)
+    }
+}
+
+/// Collect a chain's blocks into an ascending vector.
+fn blocks(chain: &reth_provider::Chain) -> Vec<SealedBlockWithSenders> {
+    chain.blocks_iter().cloned().collect()
+}
+
+/// Error raised while running a [`CanonStateRunner`].
+#[derive(Debug, thiserror::Error)]
+pub enum CanonRunnerError<E: std::error::Error> {
+    /// A provider read failed during backfill.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// The handler rejected a change.
+    #[error("canonical state handler failed")]
+    Handler(#[source] E),
+}
@@ -0,0 +1,113 @@
+//! Chain-specific wiring for the read-only [`EthApi`].
+//!
+//! `get_reth_api` used to hardcode the Ethereum mainnet components. To make the
+//! example chain agnostic we pull the pieces that vary per chain behind a small
+//! trait: the node-types adapter, the EVM config, the executor provider and the
+//! consensus implementation. Selecting a chain is then a matter of picking the
+//! matching [`RethChain`] implementation and handing it a chainspec, mirroring
+//! how the real node components are parameterized over the chainspec.
+
+use std::sync::Arc;
+
+use reth_beacon_consensus::EthBeaconConsensus;
+use reth_chainspec::{ChainSpec, EthChainSpec, EthereumHardforks};
+use reth_consensus::FullConsensus;
+use reth_db::DatabaseEnv;
+use reth_evm::execute::BlockExecutorProvider;
+use reth_evm::ConfigureEvm;
+use reth_node_ethereum::{EthEvmConfig, EthExecutorProvider, EthereumNode};
+use reth_node_types::{NodeTypesWithDB, NodeTypesWithDBAdapter};
+use reth_primitives::Header;
+
+/// The set of chain-specific components needed to wire up a read-only node.
+///
+/// An implementation ties together the node-types adapter used by the provider
+/// factory with the EVM config, executor provider and consensus that match a
+/// given chainspec. Callers parameterize [`crate::get_reth_api`] over the
+/// implementation, e.g. `get_reth_api::<EthereumChain>(path, spec, None)`.
+pub trait RethChain {
+    /// The chainspec type understood by this chain's components.
+    type ChainSpec: EthChainSpec + EthereumHardforks + 'static;
+    /// The node-types adapter backing the provider factory and blockchain tree.
+    ///
+    /// It carries this chain's [`ChainSpec`](Self::ChainSpec) and is read-only,
+    /// so the database environment is always an `Arc<DatabaseEnv>`.
+    type Node: NodeTypesWithDB<ChainSpec = Self::ChainSpec, DB = Arc<DatabaseEnv>>;
+    /// The EVM configuration used for execution and state caching.
+    type EvmConfig: ConfigureEvm<Header = Header> + Clone + 'static;
+    /// The executor provider fed to the blockchain tree externals.
+    type Executor: BlockExecutorProvider;
+
+    /// Build the EVM config for `spec`.
+    fn evm_config(spec: Arc<Self::ChainSpec>) -> Self::EvmConfig;
+
+    /// Build the executor provider for `spec`.
+    fn executor(spec: Arc<Self::ChainSpec>) -> Self::Executor;
+
+    /// Build the consensus implementation for `spec`.
+    fn consensus(spec: Arc<Self::ChainSpec>) -> Arc<dyn FullConsensus>;
+}
+
+/// Ethereum L1 wiring: `EthereumNode`, `EthEvmConfig`, `EthExecutorProvider` and
+/// `EthBeaconConsensus`. Works for mainnet as well as any named testnet loaded
+/// from a genesis JSON into a [`ChainSpec`].
+#[derive(Debug, Clone, Copy)]
+pub struct EthereumChain;
+
+impl RethChain for EthereumChain {
+    type Node = NodeTypesWithDBAdapter<EthereumNode, Arc<DatabaseEnv>>;
+    type ChainSpec = ChainSpec;
+    type EvmConfig = EthEvmConfig;
+    type Executor = EthExecutorProvider;
+
+    fn evm_config(spec: Arc<Self::ChainSpec>) -> Self::EvmConfig {
+        EthEvmConfig::new(spec)
+    }
+
+    fn executor(spec: Arc<Self::ChainSpec>) -> Self::Executor {
+        EthExecutorProvider::ethereum(spec)
+    }
+
+    fn consensus(spec: Arc<Self::ChainSpec>) -> Arc<dyn FullConsensus> {
+        Arc::new(EthBeaconConsensus::new(spec))
+    }
+}
+
+/// Optimism / OP-stack wiring, gated behind the `optimism` feature so the L1
+/// build does not pull in the `reth-optimism-*` crates. Selects the OP EVM
+/// config, executor and consensus and drives them from an `OpChainSpec`.
+#[cfg(feature = "optimism")]
+pub use optimism::OptimismChain;
+
+#[cfg(feature = "optimism")]
+mod optimism {
+    use super::*;
+
+    use reth_optimism_chainspec::OpChainSpec;
+    use reth_optimism_consensus::OpBeaconConsensus;
+    use reth_optimism_evm::{OpEvmConfig, OpExecutorProvider};
+    use reth_optimism_node::OpNode;
+
+    /// OP-stack counterpart to [`EthereumChain`].
+    #[derive(Debug, Clone, Copy)]
+    pub struct OptimismChain;
+
+    impl RethChain for OptimismChain {
+        type Node = NodeTypesWithDBAdapter<OpNode, Arc<DatabaseEnv>>;
+        type ChainSpec = OpChainSpec;
+        type EvmConfig = OpEvmConfig;
+        type Executor = OpExecutorProvider;
+
+        fn evm_config(spec: Arc<Self::ChainSpec>) -> Self::EvmConfig {
+            OpEvmConfig::new(spec)
+        }
+
+        fn executor(spec: Arc<Self::ChainSpec>) -> Self::Executor {
+            OpExecutorProvider::optimism(spec)
+        }
+
+        fn consensus(spec: Arc<Self::ChainSpec>) -> Arc<dyn FullConsensus> {
+            Arc::new(OpBeaconConsensus::new(spec))
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! The handle returned by [`crate::get_reth_api`].
+//!
+//! Bundles the [`EthApi`] with the read-only extras this crate layers on top of
+//! it: [`RethHandle::call_with_overrides`], which simulates a transaction
+//! against a frozen historical state with injected balances/code by overlaying a
+//! [`CacheDB`] on a [`RethStateDb`]; [`RethHandle::call_many`] for bundle
+//! simulation; and timed `call`/`estimate_gas` wrappers. Tracing is not on the
+//! handle; it is built separately by `crate::trace::get_trace_api`.
+
+use alloy_eips::{BlockId, BlockNumberOrTag};
+use alloy_primitives::Bytes;
+use alloy_rpc_types::simulate::MAX_SIMULATE_BLOCKS;
+use alloy_rpc_types::state::StateOverride;
+use alloy_rpc_types::{Bundle, EthCallResponse, StateContext, TransactionRequest};
+use reth_rpc_eth_types::EvmOverrides;
+use reth_errors::ProviderError;
+use reth_primitives::Header;
+use reth_provider::{BlockIdReader, HeaderProvider};
+use reth_rpc_eth_api::helpers::{EthApiSpec, EthCall};
+use reth_rpc_eth_types::EthApiError;
+use revm::db::CacheDB;
+use revm::primitives::{EnvWithHandlerCfg, ExecutionResult, ResultAndState, TxKind, U256};
+use revm::DatabaseCommit;
+
+use crate::chain::RethChain;
+use crate::metrics::RpcMetrics;
+use crate::state::RethStateDb;
+use crate::RethApi;
+
+/// The bundle handed back by [`crate::get_reth_api`].
+pub struct RethHandle<C: RethChain> {
+    eth: RethApi<C>,
+}
+
+impl<C: RethChain> RethHandle<C> {
+    /// Wrap an [`EthApi`] in the crate handle.
+    pub fn new(eth: RethApi<C>) -> Self {
+        Self { eth }
+    }
+
+    /// The underlying [`EthApi`].
+    pub fn eth(&self) -> &RethApi<C> {
+        &self.eth
+    }
+}
+
+impl<C: RethChain> RethHandle<C>
+where
+    RethApi<C>: EthApiSpec,
+{
+    /// Simulate `tx` against the state at `block_id`, seeding the `CacheDB` with
+    /// `overrides` (injected balances, nonces, code and storage slots) before
+    /// executing.
+    ///
+    /// Execution is read-only: the resulting state changes are discarded and
+    /// nothing is committed, neither to the `CacheDB` nor to the underlying
+    /// database, so callers can explore "what-if" scenarios against historical
+    /// state without a live node.
+    pub fn call_with_overrides(
+        &self,
+        tx: TransactionRequest,
+        block_id: BlockId,
+        overrides: StateOverride,
+    ) -> Result<ExecutionResult, EthApiError> {
+        let timer = RpcMetrics::timer("call_with_overrides");
+        let result = self.call_with_overrides_inner(tx, block_id, overrides);
+        timer.finish(&result);
+        result
+    }
+
+    fn call_with_overrides_inner(
+        &self,
+        tx: TransactionRequest,
+        block_id: BlockId,
+        overrides: StateOverride,
+    ) -> Result<ExecutionResult, EthApiError> {
+        let provider = self.eth.provider().clone();
+        let evm_config = self.eth.evm_config().clone();
+
+        // Resolve the historical header so execution runs with that block's
+        // number/timestamp/basefee/coinbase context, not a default (all-zero)
+        // one — otherwise NUMBER/TIMESTAMP/BASEFEE/BLOCKHASH and the basefee gas
+        // checks would be wrong for the very state we are reading.
+        let block_hash = provider
+            .block_hash_for_id(block_id)?
+            .ok_or(EthApiError::HeaderNotFound(block_id))?;
+        let header = provider
+            .header(&block_hash)?
+            .ok_or(EthApiError::HeaderNotFound(block_id))?;
+
+        let state_db = RethStateDb::new(provider, block_id)?;
+        let mut db = CacheDB::new(state_db);
+        apply_state_overrides(overrides, &mut db)?;
+
+        let ResultAndState { result, .. } = execute(&evm_config, &mut db, &header, tx)?;
+        Ok(result)
+    }
+}
+
+impl<C: RethChain> RethHandle<C>
+where
+    RethApi<C>: EthCall,
+{
+    /// Simulate a sequence of `bundles` on top of the state selected by
+    /// `state_context`, returning the per-call results for each bundle.
+    ///
+    /// The transactions within a bundle execute sequentially, and each bundle
+    /// stacks on top of the state left by the previous one, so bundle N+1 sees
+    /// bundle N's writes. The result vectors line up one-to-one with the
+    /// bundles, and each inner vector with that bundle's transactions. Bundles
+    /// are the simulated blocks, so their count is capped at
+    /// [`MAX_SIMULATE_BLOCKS`].
+    ///
+    /// Unless the target block is pending, the supplied [`BlockId`] is resolved
+    /// to its concrete block *hash* up front and the whole request is simulated
+    /// against that hash. This pins everything to one snapshot, so a reorg
+    /// landing mid-request cannot slip a different block under the state, header
+    /// and base-fee lookups.
+    pub async fn call_many(
+        &self,
+        bundles: Vec<Bundle>,
+        state_context: StateContext,
+        state_override: Option<StateOverride>,
+    ) -> Result<Vec<Vec<EthCallResponse>>, EthApiError> {
+        let timer = RpcMetrics::timer("call_many");
+        let result = async {
+            if bundles.len() as u64 > MAX_SIMULATE_BLOCKS {
+                return Err(EthApiError::InvalidParams(format!(
+                    "too many bundles: {} exceeds the {MAX_SIMULATE_BLOCKS}-block simulation cap",
+                    bundles.len(),
+                )));
+            }
+
+            let state_context = self.resolve_state_context(state_context)?;
+
+            // Hand all bundles to reth in one call so they stack on each other's
+            // state instead of each re-running against the same pinned snapshot.
+            self.eth
+                .call_many(bundles, Some(state_context), state_override)
+                .await
+        }
+        .await;
+        timer.finish(&result);
+        result
+    }
+
+    /// `eth_call` against the inner [`EthApi`], timed under the `call` method
+    /// label so the primary RPC surface is covered by [`RpcMetrics`] too.
+    pub async fn call(
+        &self,
+        request: TransactionRequest,
+        block_number: Option<BlockId>,
+        overrides: EvmOverrides,
+    ) -> Result<Bytes, EthApiError> {
+        let timer = RpcMetrics::timer("call");
+        let result = self.eth.call(request, block_number, overrides).await;
+        timer.finish(&result);
+        result
+    }
+
+    /// `eth_estimateGas` against the inner [`EthApi`], timed under the
+    /// `estimate_gas` method label.
+    pub async fn estimate_gas(
+        &self,
+        request: TransactionRequest,
+        block_number: Option<BlockId>,
+        state_override: Option<StateOverride>,
+    ) -> Result<U256, EthApiError> {
+        let timer = RpcMetrics::timer("estimate_gas");
+        let result = self
+            .eth
+            .estimate_gas(request, block_number, state_override)
+            .await;
+        timer.finish(&result);
+        result
+    }
+
+    /// Resolve a [`StateContext`]'s target block to a concrete block hash so the
+    /// rest of the request reads a consistent snapshot. A pending target is left
+    /// untouched, since it has no hash yet.
+    fn resolve_state_context(
+        &self,
+        mut ctx: StateContext,
+    ) -> Result<StateContext, EthApiError> {
+        let block_id = ctx.block_number.unwrap_or_default();
+        let resolved = match block_id {
+            BlockId::Number(BlockNumberOrTag::Pending) => block_id,
+            other => {
+                let hash = self
+                    .eth
+                    .provider()
+                    .block_hash_for_id(other)?
+                    .ok_or(EthApiError::HeaderNotFound(other))?;
+                BlockId::Hash(hash.into())
+            }
+        };
+        ctx.block_number = Some(resolved);
+        Ok(ctx)
+    }
+}
+
+/// Seed a [`CacheDB`] with a set of account/storage overrides.
+fn apply_state_overrides<DB>(
+    overrides: StateOverride,
+    db: &mut CacheDB<DB>,
+) -> Result<(), EthApiError>
+where
+    DB: revm::DatabaseRef<Error = ProviderError>,
+{
+    for (address, account) in overrides {
+        let mut info = db.basic_ref(address)?.unwrap_or_default();
+        if let Some(balance) = account.balance {
+            info.balance = balance;
+        }
+        if let Some(nonce) = account.nonce {
+            info.nonce = nonce;
+        }
+        if let Some(code) = account.code {
+            info.code = Some(revm::primitives::Bytecode::new_raw(code));
+        }
+        db.insert_account_info(address, info);
+
+        if let Some(state) = account.state {
+            for (slot, value) in state {
+                db.insert_account_storage(address, slot.into(), value.into())?;
+            }
+        }
+        if let Some(diff) = account.state_diff {
+            for (slot, value) in diff {
+                db.insert_account_storage(address, slot.into(), value.into())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Build an EVM over `db` from `tx`, in the block context of `header`, and run
+/// it to completion.
+fn execute<C, DB>(
+    evm_config: &C,
+    db: &mut DB,
+    header: &Header,
+    tx: TransactionRequest,
+) -> Result<ResultAndState, EthApiError>
+where
+    C: reth_evm::ConfigureEvm<Header = Header>,
+    DB: revm::Database + DatabaseCommit,
+    EthApiError: From<revm::primitives::EVMError<DB::Error>>,
+{
+    let mut tx_env = revm::primitives::TxEnv::default();
+    if let Some(from) = tx.from {
+        tx_env.caller = from;
+    }
+    tx_env.transact_to = match tx.to {
+        Some(TxKind::Call(to)) => TxKind::Call(to),
+        _ => TxKind::Create,
+    };
+    tx_env.value = tx.value.unwrap_or(U256::ZERO);
+    tx_env.data = tx.input.into_input().unwrap_or_default();
+    tx_env.gas_limit = tx.gas.unwrap_or(u64::MAX);
+    // Honor the full 1559 fee fields, not just the legacy `gas_price`.
+    tx_env.gas_price = U256::from(tx.max_fee_per_gas.or(tx.gas_price).unwrap_or_default());
+    tx_env.gas_priority_fee = tx.max_priority_fee_per_gas.map(U256::from);
+    tx_env.nonce = tx.nonce;
+    tx_env.chain_id = tx.chain_id;
+    if let Some(access_list) = tx.access_list.clone() {
+        tx_env.access_list = access_list.0;
+    }
+
+    // Derive the cfg and block env from the historical header so the EVM sees
+    // that block's number/timestamp/basefee/coinbase/prevrandao.
+    let (mut cfg, block) = evm_config.cfg_and_block_env(header);
+    // Relax the checks an `eth_call`-style simulation must not enforce: without
+    // this a valid what-if call with no gas price reverts on the base-fee check,
+    // a sender with code trips EIP-3607 and a stale nonce is rejected.
+    cfg.disable_base_fee = true;
+    cfg.disable_eip3607 = true;
+    cfg.disable_nonce_check = true;
+    let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block, tx_env);
+    let mut evm = evm_config.evm_with_env(&mut *db, env);
+    Ok(evm.transact()?)
+}
@@ -3,15 +3,12 @@ use std::path::Path;
 use std::sync::Arc;
 
 use alloy_rpc_types::simulate::MAX_SIMULATE_BLOCKS;
-use reth_beacon_consensus::EthBeaconConsensus;
 use reth_blockchain_tree::{
     BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree, TreeExternals,
 };
 use reth_chainspec::ChainSpecBuilder;
-use reth_db::{open_db_read_only, DatabaseEnv};
+use reth_db::open_db_read_only;
 use reth_network_api::noop::NoopNetwork;
-use reth_node_ethereum::{EthEvmConfig, EthExecutorProvider, EthereumNode};
-use reth_node_types::NodeTypesWithDBAdapter;
 use reth_primitives::constants::ETHEREUM_BLOCK_GAS_LIMIT;
 use reth_provider::CanonStateSubscriptions;
 use reth_provider::{
@@ -31,11 +28,22 @@ use reth_transaction_pool::{
     TransactionValidationTaskExecutor,
 };
 
+mod canon;
+mod chain;
+mod handle;
+mod metrics;
+mod state;
+mod trace;
+
+use chain::{EthereumChain, RethChain};
+use handle::RethHandle;
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let reth_api = get_reth_api("your_path_to_node")?;
+    let spec = Arc::new(ChainSpecBuilder::mainnet().build());
+    let reth_api = get_reth_api::<EthereumChain>("your_path_to_node", spec, None)?;
 
-    let mut stream = reth_api.provider().subscribe_to_canonical_state();
+    let mut stream = reth_api.eth().provider().subscribe_to_canonical_state();
 
     while let Ok(notification) = stream.recv().await {
         match notification {
@@ -52,33 +60,58 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-type RethProvider = BlockchainProvider<NodeTypesWithDBAdapter<EthereumNode, Arc<DatabaseEnv>>>;
-type RethTxPool = Pool<
-    TransactionValidationTaskExecutor<EthTransactionValidator<RethProvider, EthPooledTransaction>>,
+/// The read-only [`BlockchainProvider`] parameterized over a [`RethChain`].
+type RethProvider<C> = BlockchainProvider<<C as RethChain>::Node>;
+/// The transaction pool parameterized over a [`RethChain`].
+type RethTxPool<C> = Pool<
+    TransactionValidationTaskExecutor<
+        EthTransactionValidator<RethProvider<C>, EthPooledTransaction>,
+    >,
     CoinbaseTipOrdering<EthPooledTransaction>,
     NoopBlobStore,
 >;
-type RethApi = EthApi<RethProvider, RethTxPool, NoopNetwork, EthEvmConfig>;
+/// The [`EthApi`] handle parameterized over a [`RethChain`].
+type RethApi<C> = EthApi<RethProvider<C>, RethTxPool<C>, NoopNetwork, <C as RethChain>::EvmConfig>;
+
+/// Build a read-only [`EthApi`] over the database at `path` for the chain `C`.
+///
+/// The chain-specific EVM config, executor provider and consensus are selected
+/// by the [`RethChain`] implementation, so the same wiring serves mainnet, a
+/// named testnet loaded from a genesis JSON, or an OP-stack chain — the caller
+/// only supplies the matching `chain_spec`.
+///
+/// The `EthApi` is handed back inside a [`RethHandle`], the bundle the crate's
+/// read-only extras (historical-state overrides and bundle simulation) hang off
+/// of. Tracing is built separately from the `EthApi` by `trace::get_trace_api`.
+///
+/// When `metrics` is `Some`, a Prometheus recorder is installed and a scrape
+/// endpoint bound before the task pools and caches are built, so their reth
+/// instrumentation exports through it; pass `None` to leave observability off.
+pub fn get_reth_api<C: RethChain>(
+    path: impl ToString,
+    chain_spec: Arc<C::ChainSpec>,
+    metrics: Option<metrics::MetricsConfig>,
+) -> Result<RethHandle<C>> {
+    if let Some(config) = metrics {
+        metrics::install(config)?;
+    }
 
-/// Make this chain agnostic
-pub fn get_reth_api(path: impl ToString) -> Result<RethApi> {
     let db_path = path.to_string();
     let db_path = Path::new(&db_path);
     let db = open_db_read_only(&db_path.join("db"), Default::default())?;
-    let spec = Arc::new(ChainSpecBuilder::mainnet().build());
-    let evm_config = EthEvmConfig::new(spec.clone());
 
-    let provider_factory =
-        ProviderFactory::<NodeTypesWithDBAdapter<EthereumNode, Arc<DatabaseEnv>>>::new(
-            db.into(),
-            spec.clone(),
-            StaticFileProvider::read_only(db_path.join("static_files"), true)?,
-        );
+    let evm_config = C::evm_config(chain_spec.clone());
+
+    let provider_factory = ProviderFactory::<C::Node>::new(
+        db.into(),
+        chain_spec.clone(),
+        StaticFileProvider::read_only(db_path.join("static_files"), true)?,
+    );
 
     let tree_externals = TreeExternals::new(
         provider_factory.clone(),
-        Arc::new(EthBeaconConsensus::new(spec.clone())),
-        EthExecutorProvider::ethereum(spec.clone()),
+        C::consensus(chain_spec.clone()),
+        C::executor(chain_spec.clone()),
     );
 
     let tree_config = BlockchainTreeConfig::default();
@@ -97,11 +130,12 @@ pub fn get_reth_api(path: impl ToString) -> Result<RethApi> {
         evm_config.clone(),
     );
 
-    let transaction_validator = EthTransactionValidatorBuilder::new(spec.clone()).build_with_tasks(
-        provider.clone(),
-        task_executor.clone(),
-        NoopBlobStore::default(),
-    );
+    let transaction_validator = EthTransactionValidatorBuilder::new(chain_spec.clone())
+        .build_with_tasks(
+            provider.clone(),
+            task_executor.clone(),
+            NoopBlobStore::default(),
+        );
 
     let tx_pool = reth_transaction_pool::Pool::eth_pool(
         transaction_validator,
@@ -134,5 +168,5 @@ pub fn get_reth_api(path: impl ToString) -> Result<RethApi> {
         DEFAULT_PROOF_PERMITS,
     );
 
-    Ok(api)
+    Ok(RethHandle::new(api))
 }
@@ -0,0 +1,103 @@
+//! Opt-in observability for the read-only API factory.
+//!
+//! The factory spins up a `TokioTaskExecutor`, a `BlockingTaskPool`, an
+//! `EthStateCache`, the gas-oracle and fee-history caches and the
+//! transaction-pool validator — all of which are already instrumented with the
+//! `metrics` crate inside reth but have nowhere to export to in this example.
+//! [`install`] installs a global Prometheus recorder and binds a scrape
+//! endpoint, so those gauges and counters become Grafana-ready with no further
+//! wiring; [`RpcMetrics`] adds the per-method latency/error counters the RPC
+//! paths lack on their own.
+
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use eyre::Result;
+use metrics_exporter_prometheus::PrometheusBuilder;
+use reth_metrics::{
+    metrics::{Counter, Histogram},
+    Metrics,
+};
+
+/// Where to expose the Prometheus scrape endpoint.
+#[derive(Debug, Clone, Copy)]
+pub struct MetricsConfig {
+    /// Address the scrape endpoint listens on.
+    pub listen_addr: SocketAddr,
+}
+
+impl MetricsConfig {
+    /// Serve metrics on `listen_addr`.
+    pub fn new(listen_addr: SocketAddr) -> Self {
+        Self { listen_addr }
+    }
+}
+
+/// Install the global Prometheus recorder and bind the scrape endpoint.
+///
+/// Call once, before the factory builds the task pools and caches, so their
+/// metrics register against this recorder. Installing a recorder twice is an
+/// error, matching the `metrics` crate's global-recorder contract.
+pub fn install(config: MetricsConfig) -> Result<()> {
+    PrometheusBuilder::new()
+        .with_http_listener(config.listen_addr)
+        .install()?;
+    Ok(())
+}
+
+/// Latency and error counters for a single RPC method.
+///
+/// Build one per method with [`RpcMetrics::for_method`] and feed it the outcome
+/// of each call via [`RpcMetrics::record`]; the method name rides along as a
+/// `method` label so a single Grafana panel can break latency down by method.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "reth_api.rpc")]
+pub struct RpcMetrics {
+    /// Calls served for this method.
+    calls: Counter,
+    /// Calls that returned an error.
+    errors: Counter,
+    /// Call latency, in seconds.
+    duration_seconds: Histogram,
+}
+
+impl RpcMetrics {
+    /// A metrics handle labelled with the RPC `method` name.
+    pub fn for_method(method: &str) -> Self {
+        Self::new_with_labels(&[("method", method.to_string())])
+    }
+
+    /// Record a completed call lasting `duration` seconds, counting it as an
+    /// error when `is_err`.
+    pub fn record(&self, duration: f64, is_err: bool) {
+        self.calls.increment(1);
+        if is_err {
+            self.errors.increment(1);
+        }
+        self.duration_seconds.record(duration);
+    }
+
+    /// Start timing a call to `method`. Hand the call's [`Result`] to
+    /// [`Timer::finish`] to record its latency and error outcome.
+    pub fn timer(method: &str) -> Timer {
+        Timer {
+            metrics: Self::for_method(method),
+            start: Instant::now(),
+        }
+    }
+}
+
+/// An in-flight RPC call being timed; see [`RpcMetrics::timer`].
+#[must_use = "call `finish` to record the timed call"]
+pub struct Timer {
+    metrics: RpcMetrics,
+    start: Instant,
+}
+
+impl Timer {
+    /// Record the elapsed time and whether `result` is an error.
+    pub fn finish<T, E>(self, result: &Result<T, E>) {
+        self.metrics
+            .record(self.start.elapsed().as_secs_f64(), result.is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Historical state access for `revm`.
+//!
+//! [`RethStateDb`] wraps the read-only `BlockchainProvider` together with a
+//! [`BlockId`] and implements `revm`'s [`DatabaseRef`] by delegating to the
+//! provider's state at that block. Dropped into a [`CacheDB`] it lets callers
+//! overlay account/storage overrides and execute a transaction against a frozen
+//! historical state — the same trick heavier RPC shims use — without a live
+//! node.
+
+use std::sync::Arc;
+
+use alloy_eips::BlockId;
+use alloy_primitives::{Address, B256, U256};
+use reth_errors::ProviderError;
+use reth_provider::{BlockHashReader, StateProvider, StateProviderFactory};
+use revm::db::DatabaseRef;
+use revm::primitives::{AccountInfo, Bytecode};
+
+/// A [`DatabaseRef`] view of the provider's state at a fixed [`BlockId`].
+///
+/// Construction resolves the historical [`StateProvider`] once; every ref call
+/// then reads from that frozen snapshot, so a [`CacheDB`] built on top overlays
+/// its overrides over a consistent historical state.
+#[derive(Clone)]
+pub struct RethStateDb<P> {
+    provider: P,
+    block_id: BlockId,
+    state: Arc<Box<dyn StateProvider>>,
+}
+
+impl<P> RethStateDb<P>
+where
+    P: StateProviderFactory + BlockHashReader,
+{
+    /// Pin a historical state view at `block_id`.
+    pub fn new(provider: P, block_id: BlockId) -> Result<Self, ProviderError> {
+        let state = provider.state_by_block_id(block_id)?;
+        Ok(Self {
+            provider,
+            block_id,
+            state: Arc::new(state),
+        })
+    }
+
+    /// The block this view is pinned to.
+    pub fn block_id(&self) -> BlockId {
+        self.block_id
+    }
+}
+
+impl<P> DatabaseRef for RethStateDb<P>
+where
+    P: BlockHashReader,
+{
+    type Error = ProviderError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let Some(account) = self.state.basic_account(address)? else {
+            return Ok(None);
+        };
+        let code_hash = account.get_bytecode_hash();
+        let code = self
+            .state
+            .bytecode_by_hash(code_hash)?
+            .map(|bytecode| Bytecode::new_raw(bytecode.bytecode().clone()));
+        Ok(Some(AccountInfo {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash,
+            code,
+        }))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        Ok(self
+            .state
+            .bytecode_by_hash(code_hash)?
+            .map(|bytecode| Bytecode::new_raw(bytecode.bytecode().clone()))
+            .unwrap_or_default())
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        Ok(self
+            .state
+            .storage(address, index.into())?
+            .map(Into::into)
+            .unwrap_or_default())
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        Ok(self.state.block_hash(number)?.unwrap_or_default())
+    }
+}
@@ -0,0 +1,125 @@
+//! A tracing surface alongside the [`EthApi`].
+//!
+//! `get_reth_api` hands back a handle around an `EthApi`, which can
+//! `call`/`estimate` but cannot produce structured traces. [`get_trace_api`] builds a [`TraceApi`] on
+//! top of the same `BlockchainProvider`, `EthStateCache` and `EvmConfig`, and
+//! [`RethTraceApi`] wraps it with the three entry points consumers iterating
+//! historical ranges actually want: `trace_block`, `trace_transaction` and a
+//! `trace_block_until` that replays a block up to an optional index with a
+//! caller-supplied inspector — optionally reusing an already-loaded block so a
+//! range scan doesn't re-read the same block from disk on every call.
+
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+use alloy_rpc_types_trace::parity::{LocalizedTransactionTrace, TraceResults, TraceType};
+use reth_primitives::SealedBlockWithSenders;
+use reth_rpc::TraceApi;
+use reth_rpc_eth_api::helpers::{Trace, TraceExt};
+use reth_rpc_eth_types::EthApiError;
+use reth_tasks::pool::BlockingTaskGuard;
+use revm::inspectors::TracingInspector;
+use revm_inspectors::tracing::TracingInspectorConfig;
+
+use crate::chain::RethChain;
+use crate::metrics::RpcMetrics;
+use crate::RethApi;
+
+/// A trace handler backed by the same provider, state cache and EVM config as
+/// the [`EthApi`] it is built from.
+pub struct RethTraceApi<C: RethChain> {
+    inner: TraceApi<RethApi<C>>,
+}
+
+/// Build a [`RethTraceApi`] on top of an existing [`EthApi`].
+///
+/// The `EthApi` already owns the shared `BlockchainProvider`, `EthStateCache`
+/// and `EvmConfig`, so the trace handler reuses them rather than spinning up a
+/// second set of caches.
+pub fn get_trace_api<C: RethChain>(eth_api: RethApi<C>) -> RethTraceApi<C>
+where
+    RethApi<C>: TraceExt,
+{
+    let blocking_task_guard = BlockingTaskGuard::new(
+        reth_rpc_server_types::constants::DEFAULT_MAX_TRACING_REQUESTS,
+    );
+    RethTraceApi {
+        inner: TraceApi::new(eth_api, blocking_task_guard),
+    }
+}
+
+impl<C: RethChain> RethTraceApi<C>
+where
+    RethApi<C>: TraceExt,
+{
+    /// The underlying reth [`TraceApi`], for the full parity trace surface.
+    pub fn inner(&self) -> &TraceApi<RethApi<C>> {
+        &self.inner
+    }
+
+    /// Trace every transaction in `block_hash`, returning the parity traces.
+    pub async fn trace_block(
+        &self,
+        block_hash: B256,
+    ) -> Result<Option<Vec<LocalizedTransactionTrace>>, EthApiError> {
+        let timer = RpcMetrics::timer("trace_block");
+        let result = self.inner.trace_block(block_hash.into()).await;
+        timer.finish(&result);
+        result
+    }
+
+    /// Trace a single transaction by hash.
+    pub async fn trace_transaction(
+        &self,
+        tx_hash: B256,
+        trace_types: std::collections::HashSet<TraceType>,
+    ) -> Result<TraceResults, EthApiError> {
+        let timer = RpcMetrics::timer("trace_transaction");
+        let result = self.inner.trace_transaction(tx_hash, trace_types).await;
+        timer.finish(&result);
+        result
+    }
+
+    /// Replay the transactions in a block up to an optional `highest_index`
+    /// with a caller-supplied inspector.
+    ///
+    /// When `block` is `Some`, the already-loaded [`SealedBlockWithSenders`] is
+    /// used instead of re-fetching it from disk — the fast path for a consumer
+    /// sweeping a historical range that already holds the block in hand. `f` is
+    /// invoked once per replayed transaction with the configured inspector and
+    /// the transaction's [`revm`] result so callers can extract call graphs,
+    /// token transfers or MEV-relevant state diffs.
+    pub async fn trace_block_until<F, R>(
+        &self,
+        block_id: alloy_eips::BlockId,
+        block: Option<Arc<SealedBlockWithSenders>>,
+        highest_index: Option<u64>,
+        config: TracingInspectorConfig,
+        f: F,
+    ) -> Result<Option<Vec<R>>, EthApiError>
+    where
+        F: Fn(
+                reth_rpc_eth_api::helpers::TransactionContext,
+                TracingInspector,
+                revm::primitives::ResultAndState,
+                &revm::db::State<reth_revm::database::StateProviderDatabase<Box<dyn reth_provider::StateProvider>>>,
+            ) -> Result<R, EthApiError>
+            + Send
+            + 'static,
+        R: Send + 'static,
+    {
+        let timer = RpcMetrics::timer("trace_block_until");
+        let eth_api = self.inner.eth_api();
+        let result = eth_api
+            .trace_block_until(
+                block_id,
+                block,
+                highest_index,
+                move || TracingInspector::new(config),
+                move |tx_info, inspector, res, state, _db| f(tx_info, inspector, res, state),
+            )
+            .await;
+        timer.finish(&result);
+        result
+    }
+}